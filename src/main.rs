@@ -45,6 +45,86 @@ struct Args {
     /// Validate system configuration and environment variables
     #[arg(long)]
     validate_config: bool,
+    
+    /// List every Python interpreter and environment found on this host
+    #[arg(long)]
+    python_envs: bool,
+    
+    /// Report duplicated or conflicting GPU Python packages in the selected environment
+    #[arg(long)]
+    check_conflicts: bool,
+    
+    /// Detect GPU-accelerated Python libraries and run a GPU smoke test for each
+    #[arg(long)]
+    frameworks: bool,
+    
+    /// Check flash-attn, xformers, bitsandbytes, vLLM and Triton against torch, CUDA and the GPUs
+    #[arg(long)]
+    llm_stack: bool,
+    
+    /// Run torch.utils.collect_env, parse it and compare with cuda-doctor's own detection
+    #[arg(long)]
+    collect_env: bool,
+    
+    /// Dump TensorFlow's build info and check that the CUDA libraries it expects resolve
+    #[arg(long)]
+    tf_build_info: bool,
+    
+    /// Explain why torch.cuda.is_available() returns False, most likely cause first
+    #[arg(long)]
+    why_no_cuda: bool,
+    
+    /// Show every copy of each CUDA library, which one the loader picks, and stubs or stale copies
+    #[arg(long)]
+    check_shadowing: bool,
+    
+    /// Report NVIDIA kernel modules, nouveau, blacklists, version mismatches, DKMS and Secure Boot
+    #[arg(long)]
+    kernel_modules: bool,
+    
+    /// Report persistence, ECC, compute and MIG modes per GPU, including changes pending a reboot
+    #[arg(long)]
+    gpu_modes: bool,
+    
+    /// JSON file of expected GPU modes to check with --gpu-modes or --validate-config (which also reads CUDA_DOCTOR_GPU_POLICY)
+    #[arg(long, value_name = "FILE")]
+    gpu_policy: Option<String>,
+    
+    /// Check WSL2 GPU support: /dev/dxg, the Windows driver libraries and conflicting Linux driver packages
+    #[arg(long)]
+    wsl: bool,
+    
+    /// Detect Docker/Podman/Kubernetes and check GPU passthrough or the host's NVIDIA container runtime
+    #[arg(long)]
+    container: bool,
+    
+    /// Show which physical GPUs CUDA_VISIBLE_DEVICES selects and how frameworks number them
+    #[arg(long)]
+    visible_devices: bool,
+    
+    /// Check that package indexes and download sites are reachable, honoring proxies and CA bundles
+    #[arg(long)]
+    check_network: bool,
+    
+    /// Endpoint URL to check instead of the defaults (repeatable; also CUDA_DOCTOR_ENDPOINTS)
+    #[arg(long, value_name = "URL")]
+    endpoint: Vec<String>,
+    
+    /// Skip the network reachability checks in --validate-config (air-gapped hosts)
+    #[arg(long)]
+    offline: bool,
+    
+    /// Internal: dlopen a library in this process and print the result as JSON
+    #[arg(long, hide = true, value_name = "LIBRARY")]
+    dlopen_probe: Option<String>,
+    
+    /// Run Python-based checks with this interpreter or venv directory
+    #[arg(long, value_name = "PATH", conflicts_with = "conda_env")]
+    python: Option<String>,
+    
+    /// Run Python-based checks inside this conda environment (name or prefix)
+    #[arg(long, value_name = "NAME")]
+    conda_env: Option<String>,
 }
 
 fn main() {
@@ -52,19 +132,33 @@ fn main() {
     let verbose = args.verbose;
     let showfix = args.showfix;
     
+    // Child side of the isolated library loading probe
+    if let Some(library) = &args.dlopen_probe {
+        run_dlopen_probe(library);
+        return;
+    }
+    
+    let python = match PythonTarget::from_args(args.python.as_deref(), args.conda_env.as_deref(), verbose) {
+        Ok(python) => python,
+        Err(e) => {
+            println!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+    
     // Handle special modes that don't require standard diagnostics
     if let Some(export_file) = &args.export {
-        export_environment(export_file, verbose);
+        export_environment(export_file, &python, verbose);
         return;
     }
     
     if let Some(import_file) = &args.import {
-        import_environment(import_file, verbose);
+        import_environment(import_file, &python, verbose);
         return;
     }
     
     if args.sysinfo {
-        show_system_info(verbose);
+        show_system_info(&python, verbose);
         return;
     }
     
@@ -74,12 +168,82 @@ fn main() {
     }
     
     if args.check_updates {
-        check_for_updates(verbose);
+        check_for_updates(&python, verbose);
         return;
     }
     
     if args.validate_config {
-        validate_configuration(verbose);
+        validate_configuration(&python, &args.endpoint, args.gpu_policy.as_deref(), args.offline, verbose);
+        return;
+    }
+    
+    if args.python_envs {
+        show_python_environments(verbose);
+        return;
+    }
+    
+    if args.check_conflicts {
+        show_package_conflicts(&python, verbose);
+        return;
+    }
+    
+    if args.frameworks {
+        show_frameworks(&python, verbose);
+        return;
+    }
+    
+    if args.llm_stack {
+        check_llm_stack(&python, verbose);
+        return;
+    }
+    
+    if args.collect_env {
+        show_torch_collect_env(&python, verbose);
+        return;
+    }
+    
+    if args.tf_build_info {
+        show_tensorflow_build_info(&python, verbose);
+        return;
+    }
+    
+    if args.why_no_cuda {
+        show_torch_cuda_diagnosis(&python, verbose);
+        return;
+    }
+    
+    if args.check_shadowing {
+        show_library_shadowing(&python, verbose);
+        return;
+    }
+    
+    if args.kernel_modules {
+        show_kernel_modules(verbose);
+        return;
+    }
+    
+    if args.gpu_modes || args.gpu_policy.is_some() {
+        show_gpu_modes(args.gpu_policy.as_deref(), verbose);
+        return;
+    }
+    
+    if args.wsl {
+        show_wsl_environment(verbose);
+        return;
+    }
+    
+    if args.container {
+        show_container_environment(verbose);
+        return;
+    }
+    
+    if args.visible_devices {
+        show_visible_devices(verbose);
+        return;
+    }
+    
+    if args.check_network {
+        show_network_reachability(&args.endpoint, verbose);
         return;
     }
     
@@ -133,16 +297,31 @@ fn main() {
             }
         },
         Err(_) => {
-            println!(" ❌ Not found");
-            if showfix {
-                println!("\n{}\n", suggest_nvidia_driver_fix());
+            // A half-applied driver upgrade, or a broken WSL setup, looks like a missing driver to nvidia-smi
+            let checks = if detect_wsl().is_some() {
+                check_wsl_environment(verbose)
+            } else {
+                check_driver_version_consistency(&DriverInstallation::detect(verbose))
+            };
+            let mismatches: Vec<CheckResult> = checks
+                .into_iter()
+                .filter(|r| r.status != CheckStatus::Pass)
+                .collect();
+            if mismatches.is_empty() {
+                println!(" ❌ Not found");
+                if showfix {
+                    println!("\n{}\n", suggest_nvidia_driver_fix());
+                }
+            } else {
+                println!(" ❌ Installed but not usable");
+                print_check_results(&mismatches, "   ");
             }
         },
     }
     
     // Check CUDA Toolkit
     print!("⚙️  Checking CUDA Toolkit...");
-    match get_cuda_toolkit_version(verbose) {
+    match get_cuda_toolkit_version(&python, verbose) {
         Ok(cuda_version) => {
             if verbose {
                 println!("\n   ✅ CUDA Version: {}", cuda_version.trim());
@@ -160,7 +339,7 @@ fn main() {
     
     // Check cuDNN
     print!("🧠 Checking cuDNN...");
-    match get_cudnn_version(verbose) {
+    match get_cudnn_version(&python, verbose) {
         Ok(cudnn_version) => {
             if verbose {
                 println!("\n   ✅ cuDNN Version: {}", cudnn_version.trim());
@@ -178,7 +357,7 @@ fn main() {
     
     // Check TensorFlow
     print!("🔥 Checking TensorFlow...");
-    match get_tensorflow_version(verbose) {
+    match get_tensorflow_version(&python, verbose) {
         Ok(tf_version) => {
             if verbose {
                 println!("\n   ✅ TensorFlow Version: {}", tf_version.trim());
@@ -196,7 +375,7 @@ fn main() {
     
     // Check PyTorch
     print!("🚀 Checking PyTorch...");
-    match get_pytorch_version(verbose) {
+    match get_pytorch_version(&python, verbose) {
         Ok(pytorch_version) => {
             if verbose {
                 println!("\n   ✅ PyTorch Version: {}", pytorch_version.trim());
@@ -212,10 +391,50 @@ fn main() {
         },
     }
     
+    // Check JAX
+    print!("🧪 Checking JAX...");
+    match get_jax_version(&python, verbose) {
+        Ok(jax_version) => {
+            if verbose {
+                println!("\n   ✅ JAX Version: {}", jax_version.trim());
+            } else {
+                println!(" ✅ Found");
+            }
+        },
+        // JAX is optional, like the other non-core frameworks, so its absence is not an error
+        Err(_) => {
+            println!(" ➖ Not installed");
+            if showfix {
+                println!("\n{}\n", suggest_jax_fix());
+            }
+        },
+    }
+    
+    // Check which CUDA build of each framework is installed
+    print!("🏗️  Checking Framework Builds...");
+    let builds = analyze_framework_builds(&python, verbose);
+    let build_warnings = builds.warnings(get_nvidia_driver_version(false).is_ok());
+    if builds.builds().is_empty() {
+        println!(" ❌ No frameworks installed");
+    } else if build_warnings.is_empty() {
+        println!(" ✅ OK");
+    } else {
+        println!(" ⚠️  CPU-only build");
+        for warning in &build_warnings {
+            println!("   ⚠️  {}", warning);
+        }
+        if showfix {
+            println!("\n{}\n", suggest_cpu_only_build_fix());
+        }
+    }
+    if verbose {
+        print!("{}", format_framework_builds(&builds));
+    }
+    
     // Run benchmarks if requested
     if args.benchmark {
         println!("\n🔬 Running Performance Benchmarks...");
-        run_benchmarks(verbose);
+        run_benchmarks(&python, verbose);
     }
     
     println!("\n=== CUDA Doctor Diagnostics Complete ===");
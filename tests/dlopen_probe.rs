@@ -0,0 +1,112 @@
+#![cfg(unix)]
+
+// Drives the real --dlopen-probe child process against small stub libraries built with the system C compiler
+
+use cuda_doctor::{probe_library_loading_with, DlopenResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+const PROBE_EXE: &str = env!("CARGO_BIN_EXE_cuda-doctor");
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cuda-doctor-dlopen-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Compiles `source` into `dir/output`; returns false when no C compiler is available
+fn compile_library(dir: &Path, output: &str, source: &str, extra_args: &[&str]) -> bool {
+    let source_path = dir.join(format!("{}.c", output));
+    fs::write(&source_path, source).unwrap();
+    let status = Command::new("cc")
+        .args(["-shared", "-fPIC", "-o"])
+        .arg(dir.join(output))
+        .arg(&source_path)
+        .args(extra_args)
+        .status();
+    match status {
+        Ok(status) => {
+            assert!(status.success(), "cc failed to build {}", output);
+            true
+        }
+        Err(_) => {
+            eprintln!("skipping: no C compiler available");
+            false
+        }
+    }
+}
+
+fn probe(library: &Path, timeout: Duration) -> DlopenResult {
+    probe_library_loading_with(Path::new(PROBE_EXE), library.to_str().unwrap(), timeout, false)
+}
+
+#[test]
+fn loads_a_plain_library() {
+    let dir = scratch_dir("plain");
+    if !compile_library(&dir, "libplain.so", "int plain_answer(void) { return 42; }\n", &[]) {
+        return;
+    }
+    let result = probe(&dir.join("libplain.so"), Duration::from_secs(30));
+    assert!(result.loaded, "{:?}", result.error);
+    assert_eq!(result.loaded_path.as_deref(), dir.join("libplain.so").to_str());
+    assert_eq!(result.cu_init, None);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reports_a_missing_dependency() {
+    let dir = scratch_dir("missing");
+    if !compile_library(&dir, "libgone.so", "int gone(void) { return 1; }\n", &[]) {
+        return;
+    }
+    let dir_arg = format!("-L{}", dir.display());
+    compile_library(&dir, "libneedsgone.so", "int gone(void);\nint needs(void) { return gone(); }\n", &[&dir_arg, "-lgone"]);
+    fs::remove_file(dir.join("libgone.so")).unwrap();
+
+    let result = probe(&dir.join("libneedsgone.so"), Duration::from_secs(30));
+    assert!(!result.loaded);
+    let error = result.error.unwrap_or_default();
+    assert!(error.contains("libgone.so"), "unexpected error: {}", error);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn calls_cuinit_and_reads_the_driver_version() {
+    let dir = scratch_dir("cuinit");
+    let source = r#"
+int cuInit(unsigned int flags) { (void)flags; return 100; }
+int cuGetErrorName(int error, const char **name) {
+    if (error != 100) return 1;
+    *name = "CUDA_ERROR_NO_DEVICE";
+    return 0;
+}
+int cuDriverGetVersion(int *version) { *version = 12040; return 0; }
+"#;
+    if !compile_library(&dir, "libcuda.so.1", source, &[]) {
+        return;
+    }
+    let result = probe(&dir.join("libcuda.so.1"), Duration::from_secs(30));
+    assert!(result.loaded, "{:?}", result.error);
+    assert_eq!(result.cu_init, Some(100));
+    assert_eq!(result.cu_init_error.as_deref(), Some("CUDA_ERROR_NO_DEVICE"));
+    assert_eq!(result.driver_api_version_string().as_deref(), Some("12.4"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn kills_a_probe_that_hangs() {
+    let dir = scratch_dir("hang");
+    let source = "#include <unistd.h>\n__attribute__((constructor)) static void hang(void) { sleep(60); }\n";
+    if !compile_library(&dir, "libhang.so", source, &[]) {
+        return;
+    }
+    let started = std::time::Instant::now();
+    let result = probe(&dir.join("libhang.so"), Duration::from_secs(1));
+    assert!(started.elapsed() < Duration::from_secs(30));
+    assert!(!result.loaded);
+    assert!(result.error.unwrap_or_default().contains("killed"));
+    fs::remove_dir_all(&dir).unwrap();
+}